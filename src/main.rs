@@ -1,15 +1,24 @@
-use std::{cmp::{max, min}, collections::HashSet, fs::{self, File}, io::{stdout, Write}, path::PathBuf, process::Command, time::Duration};
+use std::{cmp::{max, min}, collections::{HashMap, HashSet}, fs::{self, File}, io::{stdout, Read, Seek, SeekFrom, Write}, path::PathBuf, process::{Child, Command}, sync::mpsc, thread, time::{Duration, SystemTime}};
 use crossterm::{cursor, event::{self, Event, KeyCode, KeyEvent}, style::{self, Color, Stylize}, terminal, ExecutableCommand, QueueableCommand};
 use serde::{Serialize, Deserialize};
 
 type Err = Box<dyn std::error::Error>;
 
+#[derive(Clone)]
+struct MediaInfo {
+    duration_seconds: f64,
+    width: u16,
+    height: u16,
+    codec: String,
+}
+
 #[derive(Clone)]
 struct Entry {
     path: PathBuf,
     name: String,
     is_file: bool,
-    is_watched: bool,
+    watch_progress: Option<f64>,
+    media_info: Option<MediaInfo>,
 }
 
 struct State {
@@ -18,19 +27,63 @@ struct State {
     entries: Vec<Entry>,
     show_hidden: bool,
     show_help: bool,
+    show_info: bool,
+    media_cache: HashMap<PathBuf, Option<MediaInfo>>,
+    dir_cache: HashMap<PathBuf, (SystemTime, Vec<(PathBuf, String, bool)>)>,
+    scan_rx: Option<mpsc::Receiver<ScanResult>>,
+    loading: bool,
+    filtering: bool,
+    filter_query: String,
+    pending_key: Option<PendingKey>,
+    show_duplicates: bool,
+    duplicate_groups: Vec<Vec<PathBuf>>,
+    duplicate_index: usize,
+    duplicate_scanning: bool,
+    duplicate_rx: Option<mpsc::Receiver<Vec<Vec<PathBuf>>>>,
+    confirm_delete: bool,
+    renaming: bool,
+    rename_buffer: String,
+    playing: Option<(PathBuf, Child)>,
+    watch_later_misses: HashSet<PathBuf>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
+struct ScanResult {
+    path: PathBuf,
+    mtime: SystemTime,
+    entries: Vec<(PathBuf, String, bool)>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
 struct Config {
     media_dir: PathBuf,
     data_dir: PathBuf,
     player: String,
     filetypes: Vec<String>,
+    #[serde(default = "default_finish_fraction")]
+    finish_fraction: f64,
+    #[serde(default = "default_show_icons")]
+    show_icons: bool,
+}
+
+fn default_finish_fraction() -> f64 {
+    return 0.9;
+}
+
+fn default_show_icons() -> bool {
+    return true;
 }
 
 #[derive(Serialize, Deserialize, Debug)]
 struct Data {
-    history: HashSet<PathBuf>,
+    #[serde(default)]
+    history: HashMap<PathBuf, f64>,
+    #[serde(default)]
+    bookmarks: HashMap<char, PathBuf>,
+}
+
+enum PendingKey {
+    Bookmark,
+    Jump,
 }
 
 fn main() -> Result<(), Err> {
@@ -39,12 +92,14 @@ fn main() -> Result<(), Err> {
         data_dir: dirs::config_dir().unwrap().join("mlib"),
         player: String::from("mpv"),
         filetypes: Vec::from([".mp4", ".mkv", ".avi", ".m4v", ".webm", ".mov"].map(|s| s.to_string())),
+        finish_fraction: 0.9,
+        show_icons: true,
     };
 
     let config_dir = dirs::config_dir().unwrap().join("mlib");
     if fs::exists(&config_dir)? && fs::exists(config_dir.join("config.json"))? {
         let s = fs::read_to_string(config_dir.join("config.json"))?;
-        config = serde_json::from_str(s.as_str())?;
+        config = serde_json::from_str(s.as_str()).unwrap_or(config);
     } else {
         if !fs::exists(&config_dir)? {
             fs::create_dir(&config_dir)?;
@@ -59,15 +114,34 @@ fn main() -> Result<(), Err> {
         entries: Vec::new(),
         show_hidden: false,
         show_help: false,
+        show_info: false,
+        media_cache: HashMap::new(),
+        dir_cache: HashMap::new(),
+        scan_rx: None,
+        loading: false,
+        filtering: false,
+        filter_query: String::new(),
+        pending_key: None,
+        show_duplicates: false,
+        duplicate_groups: Vec::new(),
+        duplicate_index: 0,
+        duplicate_scanning: false,
+        duplicate_rx: None,
+        confirm_delete: false,
+        renaming: false,
+        rename_buffer: String::new(),
+        playing: None,
+        watch_later_misses: HashSet::new(),
     };
 
     let mut data = Data {
-        history: HashSet::new(),
+        history: HashMap::new(),
+        bookmarks: HashMap::new(),
     };
 
     if fs::exists(&config.data_dir)? && fs::exists(config.data_dir.join("data.json"))? {
         let s = fs::read_to_string(config.data_dir.join("data.json"))?;
-        data = serde_json::from_str(s.as_str())?;
+        data = serde_json::from_str(s.as_str()).unwrap_or(data);
     } else {
         if !fs::exists(&config.data_dir)? && !fs::symlink_metadata(&config.data_dir).is_ok() {
             fs::create_dir(&config.data_dir)?;
@@ -78,12 +152,16 @@ fn main() -> Result<(), Err> {
         }
     }
 
+    if !fs::exists(watch_later_dir(&config))? {
+        fs::create_dir_all(watch_later_dir(&config))?;
+    }
+
     terminal::enable_raw_mode()?;
     stdout().execute(cursor::Hide)?;
     stdout().execute(terminal::EnterAlternateScreen)?;
-    
-    update(&mut state, &data, &config)?;
-    draw(&state, &config)?;
+
+    update(&mut state, &mut data, &config)?;
+    draw(&state, &config, &data)?;
 
     loop {
         if event::poll(Duration::from_millis(1000))? {
@@ -96,13 +174,120 @@ fn main() -> Result<(), Err> {
                 Event::Resize(_, _) => (),
             };
 
-            update(&mut state, &data, &config)?;
-            draw(&state, &config)?;
+            update(&mut state, &mut data, &config)?;
+            draw(&state, &config, &data)?;
         }
     }
 }
 
 fn input(event: KeyEvent, state: &mut State, config: &Config, data: &mut Data) -> Result<(), Err> {
+    if state.filtering {
+        match event.code {
+            KeyCode::Esc => {
+                state.filtering = false;
+                state.filter_query.clear();
+                state.selected = 0;
+            },
+            KeyCode::Enter => state.filtering = false,
+            KeyCode::Backspace => {
+                state.filter_query.pop();
+                state.selected = 0;
+            },
+            KeyCode::Char(c) => {
+                state.filter_query.push(c);
+                state.selected = 0;
+            },
+            _ => (),
+        };
+        return Ok(());
+    }
+
+    if let Some(pending) = &state.pending_key {
+        if let KeyCode::Char(c) = event.code {
+            match pending {
+                PendingKey::Bookmark => {
+                    data.bookmarks.insert(c, state.path.clone());
+                    persist_data(data, config)?;
+                },
+                PendingKey::Jump => {
+                    if let Some(p) = data.bookmarks.get(&c).cloned() {
+                        state.path = p;
+                        state.selected = 0;
+                    }
+                },
+            }
+        }
+        state.pending_key = None;
+        return Ok(());
+    }
+
+    if state.show_duplicates {
+        match event.code {
+            KeyCode::Char('q') => quit()?,
+            KeyCode::Char('u') | KeyCode::Esc => state.show_duplicates = false,
+            KeyCode::Char('w') | KeyCode::Up => state.duplicate_index = state.duplicate_index.saturating_sub(1),
+            KeyCode::Char('s') | KeyCode::Down => {
+                if state.duplicate_index + 1 < state.duplicate_groups.len() {
+                    state.duplicate_index += 1;
+                }
+            },
+            _ => (),
+        };
+        return Ok(());
+    }
+
+    if state.confirm_delete {
+        match event.code {
+            KeyCode::Char('y') => {
+                if state.entries.len() > 0 && (state.selected as usize) < state.entries.len() {
+                    let entry = state.entries[state.selected as usize].clone();
+                    trash::delete(&entry.path)?;
+                    data.history.remove(&rel_path(config, &entry.path));
+                    data.bookmarks.retain(|_, p| p != &entry.path);
+                    persist_data(data, config)?;
+                }
+                state.confirm_delete = false;
+            },
+            KeyCode::Char('n') | KeyCode::Esc => state.confirm_delete = false,
+            _ => (),
+        };
+        return Ok(());
+    }
+
+    if state.renaming {
+        match event.code {
+            KeyCode::Esc => {
+                state.renaming = false;
+                state.rename_buffer.clear();
+            },
+            KeyCode::Enter => {
+                if state.entries.len() > 0 && (state.selected as usize) < state.entries.len() && !state.rename_buffer.is_empty() {
+                    let entry = state.entries[state.selected as usize].clone();
+                    let target = entry.path.with_file_name(&state.rename_buffer);
+                    // refuse to clobber an existing file/dir with the same name instead of silently replacing it
+                    if target != entry.path && !fs::exists(&target)? {
+                        fs::rename(&entry.path, &target)?;
+                        if let Some(progress) = data.history.remove(&rel_path(config, &entry.path)) {
+                            data.history.insert(rel_path(config, &target), progress);
+                        }
+                        for p in data.bookmarks.values_mut() {
+                            if p == &entry.path {
+                                *p = target.clone();
+                            }
+                        }
+                        persist_data(data, config)?;
+                    }
+                }
+                state.renaming = false;
+                state.rename_buffer.clear();
+            },
+            KeyCode::Backspace => { state.rename_buffer.pop(); },
+            KeyCode::Char(c) if c != '/' && c != std::path::MAIN_SEPARATOR => state.rename_buffer.push(c),
+            _ => (),
+        };
+        return Ok(());
+    }
+
     match event.code {
         KeyCode::Char('q') => quit()?,
         KeyCode::Char('w') | KeyCode::Up => state.selected -= 1,
@@ -122,60 +307,278 @@ fn input(event: KeyEvent, state: &mut State, config: &Config, data: &mut Data) -
         },
         KeyCode::Char('e') | KeyCode::Enter => {
             if state.entries.len() > 0 && state.selected < state.entries.len() as i32 && state.entries[state.selected as usize].is_file {
-                Command::new(&config.player)
-                    .arg(&state.entries[state.selected as usize].path)
+                let entry = state.entries[state.selected as usize].clone();
+                let child = Command::new(&config.player)
+                    .arg("--save-position-on-quit")
+                    .arg(format!("--watch-later-dir={}", watch_later_dir(config).to_string_lossy()))
+                    .arg(&entry.path)
                     .stdout(File::create("./out.log")?)
                     .stderr(File::create("./err.log")?)
                     .spawn()?;
-                hist_add(data, config, &state.entries[state.selected as usize])?;
+                if hist_progress(data, config, &entry).is_none() {
+                    hist_set(data, config, &entry, 0.0)?;
+                }
+                state.watch_later_misses.remove(&entry.path);
+                state.playing = Some((entry.path.clone(), child));
             }
         },
         KeyCode::Char('f') => {
             if state.entries.len() > 0 && state.selected < state.entries.len() as i32 && state.entries[state.selected as usize].is_file {
-                if hist_contains(data, config, &state.entries[state.selected as usize]) {
-                    hist_remove(data, config, &state.entries[state.selected as usize])?;
+                let entry = &state.entries[state.selected as usize];
+                if hist_progress(data, config, entry).is_some() {
+                    hist_remove(data, config, entry)?;
                 } else {
-                    hist_add(data, config, &state.entries[state.selected as usize])?;
+                    hist_set(data, config, entry, 1.0)?;
                 }
             }
         },
         KeyCode::Char('g') => state.show_hidden = !state.show_hidden,
         KeyCode::Char('h') => state.show_help = !state.show_help,
+        KeyCode::Char('i') => state.show_info = !state.show_info,
+        KeyCode::Char('/') => state.filtering = true,
+        KeyCode::Char('m') => state.pending_key = Some(PendingKey::Bookmark),
+        KeyCode::Char('\'') => state.pending_key = Some(PendingKey::Jump),
+        KeyCode::Char('u') => {
+            state.duplicate_groups = Vec::new();
+            state.duplicate_index = 0;
+            state.show_duplicates = true;
+            if state.duplicate_rx.is_none() {
+                state.duplicate_scanning = true;
+                state.duplicate_rx = Some(spawn_duplicate_scan(state.path.clone(), config.clone()));
+            }
+        },
+        KeyCode::Char('x') => {
+            if state.entries.len() > 0 && (state.selected as usize) < state.entries.len() {
+                state.confirm_delete = true;
+            }
+        },
+        KeyCode::Char('r') => {
+            if state.entries.len() > 0 && (state.selected as usize) < state.entries.len() {
+                state.rename_buffer = state.entries[state.selected as usize].name.clone();
+                state.renaming = true;
+            }
+        },
+        KeyCode::Esc => {
+            state.filter_query.clear();
+            state.selected = 0;
+        },
         _ => (),
     };
 
     return Ok(());
 }
 
-fn update(state: &mut State, data: &Data, config: &Config) -> Result<(), Err> {
-    let dir = fs::read_dir(&state.path);
-    if dir.is_err() {
-        return Ok(());
+fn update(state: &mut State, data: &mut Data, config: &Config) -> Result<(), Err> {
+    let player_exited = if let Some((_, child)) = &mut state.playing {
+        matches!(child.try_wait(), Ok(Some(_)))
+    } else {
+        false
+    };
+    if player_exited {
+        let (path, _) = state.playing.take().unwrap();
+        state.watch_later_misses.remove(&path);
+        if check_watch_progress(state, data, config, &path)? {
+            persist_data(data, config)?;
+        }
+    }
+
+    if let Some(rx) = &state.scan_rx {
+        if let Ok(result) = rx.try_recv() {
+            state.dir_cache.insert(result.path.clone(), (result.mtime, result.entries.clone()));
+            if result.path == state.path {
+                refresh_watch_progress(state, data, config, &result.entries)?;
+                apply_entries(state, data, config, &result.entries);
+                state.loading = false;
+            }
+            state.scan_rx = None;
+        }
+    }
+
+    if let Some(rx) = &state.duplicate_rx {
+        if let Ok(groups) = rx.try_recv() {
+            state.duplicate_groups = groups;
+            state.duplicate_index = 0;
+            state.duplicate_scanning = false;
+            state.duplicate_rx = None;
+        }
+    }
+
+    let dir_mtime = fs::metadata(&state.path).and_then(|m| m.modified()).ok();
+    let cache_fresh = state.dir_cache.get(&state.path).map_or(false, |(mtime, _)| Some(*mtime) == dir_mtime);
+
+    if cache_fresh {
+        let entries = state.dir_cache.get(&state.path).unwrap().1.clone();
+        apply_entries(state, data, config, &entries);
+        state.loading = false;
+    } else if state.scan_rx.is_none() {
+        state.scan_rx = Some(spawn_scan(state.path.clone()));
+        state.loading = true;
+    }
+
+    if state.selected < 0 || state.selected >= state.entries.len() as i32 {
+        if state.entries.len() > 0 {
+            state.selected = state.selected.rem_euclid(state.entries.len() as i32);
+        }
+    }
+
+    return Ok(());
+}
+
+// reads each file's mpv watch-later position (if any) and folds the resulting fraction into Data.history;
+// only called when a directory is actually (re)scanned, not on every update(), and skips files already
+// known to have no watch-later file so repeat scans don't re-stat/re-read them
+fn refresh_watch_progress(state: &mut State, data: &mut Data, config: &Config, raw: &Vec<(PathBuf, String, bool)>) -> Result<(), Err> {
+    let mut changed = false;
+    for (path, _, is_file) in raw {
+        if !*is_file || state.watch_later_misses.contains(path) {
+            continue;
+        }
+        if check_watch_progress(state, data, config, path)? {
+            changed = true;
+        }
+    }
+    if changed {
+        persist_data(data, config)?;
+    }
+    return Ok(());
+}
+
+// checks a single file's mpv watch-later position and writes it into Data.history if it differs from the stored value;
+// remembers a miss so future scans skip this file until it's played again
+fn check_watch_progress(state: &mut State, data: &mut Data, config: &Config, path: &PathBuf) -> Result<bool, Err> {
+    let info = state.media_cache.entry(path.clone()).or_insert_with(|| read_media_info(path)).clone();
+    let duration = match &info {
+        Some(info) if info.duration_seconds > 0.0 => info.duration_seconds,
+        _ => return Ok(false),
+    };
+    let seconds = match read_watch_later_seconds(config, path) {
+        Some(seconds) => seconds,
+        None => {
+            state.watch_later_misses.insert(path.clone());
+            return Ok(false);
+        },
+    };
+    let fraction = (seconds / duration).clamp(0.0, 1.0);
+    let rel = rel_path(config, path);
+    if data.history.get(&rel) != Some(&fraction) {
+        data.history.insert(rel, fraction);
+        return Ok(true);
     }
+    return Ok(false);
+}
+
+// rebuilds the visible Entry list (watch progress/media-info + hidden-file filtering) from a raw listing
+fn apply_entries(state: &mut State, data: &Data, config: &Config, raw: &Vec<(PathBuf, String, bool)>) {
     state.entries.clear();
-    for d in dir? {
+    for (path, name, is_file) in raw {
         let mut e = Entry {
-            path: d.as_ref().unwrap().path(),
-            name: d.as_ref().unwrap().file_name().into_string().unwrap(),
-            is_file: d.as_ref().unwrap().file_type().unwrap().is_file(),
-            is_watched: false,
+            path: path.clone(),
+            name: name.clone(),
+            is_file: *is_file,
+            watch_progress: None,
+            media_info: None,
         };
-        e.is_watched = hist_contains(data, config, &e);
+        e.watch_progress = hist_progress(data, config, &e);
+        if e.is_file {
+            e.media_info = state.media_cache.entry(e.path.clone()).or_insert_with(|| read_media_info(&e.path)).clone();
+        }
         if state.show_hidden || !e.name.starts_with(".") && e.name != "System Volume Information" && (!e.is_file || config.filetypes.iter().any(|s| e.name.ends_with(s))) {
             state.entries.push(e);
         }
     }
     state.entries.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
-    if state.selected < 0 || state.selected >= state.entries.len() as i32 {
-        if state.entries.len() > 0 {
-            state.selected = state.selected.rem_euclid(state.entries.len() as i32);
+
+    if !state.filter_query.is_empty() {
+        let mut scored: Vec<(Entry, i32)> = state.entries.drain(..)
+            .filter_map(|e| fuzzy_score(&e.name, &state.filter_query).map(|s| (e, s)))
+            .collect();
+        scored.sort_by(|a, b| b.1.cmp(&a.1));
+        state.entries = scored.into_iter().map(|(e, _)| e).collect();
+    }
+}
+
+// subsequence fuzzy match: query's characters must appear in order within name;
+// higher score for consecutive runs, word-boundary hits, and matching from the start
+fn fuzzy_score(name: &str, query: &str) -> Option<i32> {
+    let chars: Vec<char> = name.chars().collect();
+    let lower: Vec<char> = name.to_lowercase().chars().collect();
+    let query_lower: Vec<char> = query.to_lowercase().chars().collect();
+
+    let mut score = 0i32;
+    let mut ti = 0usize;
+    let mut consecutive = false;
+    for qc in &query_lower {
+        let mut found = false;
+        while ti < lower.len() {
+            if lower[ti] == *qc {
+                found = true;
+                if ti == 0 {
+                    score += 10;
+                }
+                if is_word_boundary(&chars, ti) {
+                    score += 5;
+                }
+                if consecutive {
+                    score += 8;
+                }
+                consecutive = true;
+                ti += 1;
+                break;
+            }
+            consecutive = false;
+            ti += 1;
+        }
+        if !found {
+            return None;
         }
     }
+    return Some(score);
+}
 
-    return Ok(());
+fn is_word_boundary(chars: &Vec<char>, i: usize) -> bool {
+    if i == 0 {
+        return true;
+    }
+    let prev = chars[i-1];
+    if prev == '/' || prev == '_' || prev == '-' || prev == ' ' {
+        return true;
+    }
+    return prev.is_lowercase() && chars[i].is_uppercase();
 }
 
-fn draw(state: &State, config: &Config) -> Result<(), Err> {
+// reads a directory on a worker thread so slow/network/large directories don't stall the UI
+fn spawn_scan(path: PathBuf) -> mpsc::Receiver<ScanResult> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mtime = fs::metadata(&path).and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut entries = Vec::new();
+        if let Ok(dir) = fs::read_dir(&path) {
+            for d in dir {
+                if let Ok(d) = d {
+                    if let Ok(file_type) = d.file_type() {
+                        if let Ok(name) = d.file_name().into_string() {
+                            entries.push((d.path(), name, file_type.is_file()));
+                        }
+                    }
+                }
+            }
+        }
+        let _ = tx.send(ScanResult { path, mtime, entries });
+    });
+    return rx;
+}
+
+// finds duplicates on a worker thread so scanning a large library doesn't stall the UI
+fn spawn_duplicate_scan(root: PathBuf, config: Config) -> mpsc::Receiver<Vec<Vec<PathBuf>>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let groups = scan_duplicates(&root, &config);
+        let _ = tx.send(groups);
+    });
+    return rx;
+}
+
+fn draw(state: &State, config: &Config, data: &Data) -> Result<(), Err> {
     let min_size: (i32, i32) = (0, 0);
     let max_size: (i32, i32) = (400, 20);
     let mut size: (i32, i32) = (crossterm::terminal::size()?.0 as i32, crossterm::terminal::size()?.1 as i32);
@@ -184,8 +587,11 @@ fn draw(state: &State, config: &Config) -> Result<(), Err> {
     clear()?;
     draw_rect(0, 0, size.0-1, size.1-1, Color::Red)?;
     
-    draw_text(1, 0, format!(" {0}{1} ", state.path.to_string_lossy(), if state.path.to_string_lossy() == "/" {""} else {"/"}).as_str(), size.0-2, Color::Cyan)?;
+    draw_text(1, 0, format!(" {0}{1}{2} ", state.path.to_string_lossy(), if state.path.to_string_lossy() == "/" {""} else {"/"}, if state.loading {" loading..."} else {""}).as_str(), size.0-2, Color::Cyan)?;
     draw_text(size.0-9, size.1-1, " [h]elp ", 0, Color::Cyan)?;
+    if state.filtering || !state.filter_query.is_empty() {
+        draw_text(1, size.1-1, format!(" /{} ", state.filter_query).as_str(), size.0/2, Color::Cyan)?;
+    }
 
     let mut offset: i32 = 1;
     if state.selected >= size.1 as i32 - 2 {
@@ -200,20 +606,32 @@ fn draw(state: &State, config: &Config) -> Result<(), Err> {
                 name = name.replace(&s, "");
             }
         }
-        let max_len = size.0 - if state.show_help {46} else {6};
+        if let Some(progress) = entry.watch_progress {
+            name = format!("{} ({}%)", name, (progress * 100.0).round() as i32);
+        }
+        let icon_width = if config.show_icons {2} else {0};
+        let text_x = 4 + icon_width;
+        let max_len = size.0 - if state.show_help {46} else {6} - icon_width;
         let y = i + offset;
         if y > 0 && y < size.1 - 1 {
             if state.selected == i {
                 draw_text(2, y, ">", 0, Color::White)?;
             }
             if entry.is_file {
-                if entry.is_watched {
-                    draw_text(4, y, &name, max_len, Color::Green)?;
-                } else {
-                    draw_text(4, y, &name, max_len, Color::White)?;
+                let color = match entry.watch_progress {
+                    Some(progress) if progress >= config.finish_fraction => Color::Green,
+                    Some(_) => Color::Yellow,
+                    None => Color::White,
+                };
+                if config.show_icons {
+                    draw_text(4, y, icon_for(&entry), 0, color)?;
                 }
+                draw_text(text_x, y, &name, max_len, color)?;
             } else {
-                draw_text(4, y, &((&name).to_string() + "/"), max_len, Color::Cyan)?;
+                if config.show_icons {
+                    draw_text(4, y, DIR_ICON, 0, Color::Cyan)?;
+                }
+                draw_text(text_x, y, &((&name).to_string() + "/"), max_len, Color::Cyan)?;
             }
         }
         i += 1;
@@ -230,7 +648,82 @@ fn draw(state: &State, config: &Config) -> Result<(), Err> {
         draw_text(size.0-39, min(5, size.1-3), "[f]                  toggle watched", 0, Color::Cyan)?;
         draw_text(size.0-39, min(6, size.1-3), "[g]                   toggle filter", 0, Color::Cyan)?;
         draw_text(size.0-39, min(7, size.1-3), "[h]                     toggle help", 0, Color::Cyan)?;
+        draw_text(size.0-39, min(8, size.1-3), "[i]                     toggle info", 0, Color::Cyan)?;
+        draw_text(size.0-39, min(9, size.1-3), "[/]                    fuzzy filter", 0, Color::Cyan)?;
+        draw_text(size.0-39, min(10, size.1-3), "[m][letter]            set bookmark", 0, Color::Cyan)?;
+        draw_text(size.0-39, min(11, size.1-3), "['][letter]           jump bookmark", 0, Color::Cyan)?;
+        draw_text(size.0-39, min(12, size.1-3), "[u]              toggle duplicates", 0, Color::Cyan)?;
+        draw_text(size.0-39, min(13, size.1-3), "[x]                    delete file", 0, Color::Cyan)?;
+        draw_text(size.0-39, min(14, size.1-3), "[r]                    rename file", 0, Color::Cyan)?;
         draw_text(size.0-39, size.1-3,         "[♥]                     mlib v0.1.0", 0, Color::Cyan)?;
+
+        let mut bookmarks: Vec<(&char, &PathBuf)> = data.bookmarks.iter().collect();
+        bookmarks.sort_by_key(|(k, _)| **k);
+        for (i, (key, path)) in bookmarks.iter().enumerate() {
+            let y = 16 + i as i32;
+            if y >= size.1-3 {
+                break;
+            }
+            draw_text(size.0-39, y, format!("'{} -> {}", key, path.to_string_lossy()).as_str(), 37, Color::Cyan)?;
+        }
+    }
+
+    if state.show_info && state.entries.len() > 0 && state.selected < state.entries.len() as i32 {
+        let entry = &state.entries[state.selected as usize];
+        if entry.is_file {
+            draw_fill(size.0-41, 1, size.0-3, 5, ' ', Color::Cyan)?;
+            draw_rect(size.0-41, 1, size.0-3, 5, Color::Cyan)?;
+            draw_text(size.0-40, 1, " info ", 0, Color::Cyan)?;
+
+            match &entry.media_info {
+                Some(info) => {
+                    draw_text(size.0-39, 2, format!("duration:   {:.0}s", info.duration_seconds).as_str(), 0, Color::Cyan)?;
+                    draw_text(size.0-39, 3, format!("resolution: {}x{}", info.width, info.height).as_str(), 0, Color::Cyan)?;
+                    draw_text(size.0-39, 4, format!("codec:      {}", info.codec).as_str(), 0, Color::Cyan)?;
+                },
+                None => {
+                    draw_text(size.0-39, 2, "no metadata available", 0, Color::Cyan)?;
+                },
+            }
+        }
+    }
+
+    if state.show_duplicates {
+        draw_fill(4, 2, size.0-5, size.1-3, ' ', Color::Magenta)?;
+        draw_rect(4, 2, size.0-5, size.1-3, Color::Magenta)?;
+        draw_text(5, 2, format!(" duplicates ({}/{}){} ", if state.duplicate_groups.is_empty() {0} else {state.duplicate_index+1}, state.duplicate_groups.len(), if state.duplicate_scanning {" scanning..."} else {""}).as_str(), 0, Color::Magenta)?;
+
+        match state.duplicate_groups.get(state.duplicate_index) {
+            Some(group) => {
+                for (i, path) in group.iter().enumerate() {
+                    let y = 4 + i as i32;
+                    if y >= size.1-4 {
+                        break;
+                    }
+                    draw_text(6, y, path.to_string_lossy().to_string().as_str(), size.0-12, Color::Magenta)?;
+                }
+            },
+            None if state.duplicate_scanning => {
+                draw_text(6, 4, "scanning...", 0, Color::Magenta)?;
+            },
+            None => {
+                draw_text(6, 4, "no duplicates found", 0, Color::Magenta)?;
+            },
+        }
+    }
+
+    if state.confirm_delete && state.entries.len() > 0 && state.selected < state.entries.len() as i32 {
+        let name = &state.entries[state.selected as usize].name;
+        draw_fill(4, size.1/2-2, size.0-5, size.1/2+1, ' ', Color::Red)?;
+        draw_rect(4, size.1/2-2, size.0-5, size.1/2+1, Color::Red)?;
+        draw_text(6, size.1/2-1, format!("delete \"{}\" to trash? (y/n)", name).as_str(), size.0-12, Color::Red)?;
+    }
+
+    if state.renaming {
+        draw_fill(4, size.1/2-2, size.0-5, size.1/2+1, ' ', Color::Cyan)?;
+        draw_rect(4, size.1/2-2, size.0-5, size.1/2+1, Color::Cyan)?;
+        draw_text(5, size.1/2-2, " rename ", 0, Color::Cyan)?;
+        draw_text(6, size.1/2-1, format!("{}_", state.rename_buffer).as_str(), size.0-12, Color::Cyan)?;
     }
 
     stdout().flush()?;
@@ -238,25 +731,254 @@ fn draw(state: &State, config: &Config) -> Result<(), Err> {
     return Ok(());
 }
 
-fn hist_contains(data: &Data, config: &Config, e: &Entry) -> bool {
-    return data.history.contains(&e.path.strip_prefix(&config.media_dir).unwrap_or(&e.path).to_path_buf());
+fn rel_path(config: &Config, path: &PathBuf) -> PathBuf {
+    return path.strip_prefix(&config.media_dir).unwrap_or(path).to_path_buf();
 }
 
-fn hist_add(data: &mut Data, config: &Config, e: &Entry) -> Result<(), Err> {
-    data.history.insert(e.path.strip_prefix(&config.media_dir).unwrap_or(&e.path).to_path_buf().clone());
-    if fs::exists(config.data_dir.join("data.json"))? {
-        fs::write(config.data_dir.join("data.json"), serde_json::to_string_pretty(data)?)?;
+const DIR_ICON: &str = "\u{f07b}";
+const FILE_ICON_FALLBACK: &str = "\u{f15b}";
+const FILE_ICONS: &[(&str, &str)] = &[
+    (".mp4", "\u{f03d}"),
+    (".m4v", "\u{f03d}"),
+    (".mov", "\u{f03d}"),
+    (".mkv", "\u{f008}"),
+    (".webm", "\u{e271}"),
+    (".avi", "\u{f008}"),
+];
+
+// maps a file's extension to a Nerd Font glyph; extend FILE_ICONS for new containers
+fn icon_for(entry: &Entry) -> &'static str {
+    if !entry.is_file {
+        return DIR_ICON;
     }
+    let name = entry.name.to_lowercase();
+    for (ext, icon) in FILE_ICONS {
+        if name.ends_with(ext) {
+            return icon;
+        }
+    }
+    return FILE_ICON_FALLBACK;
+}
+
+fn hist_progress(data: &Data, config: &Config, e: &Entry) -> Option<f64> {
+    return data.history.get(&rel_path(config, &e.path)).copied();
+}
+
+fn hist_set(data: &mut Data, config: &Config, e: &Entry, progress: f64) -> Result<(), Err> {
+    data.history.insert(rel_path(config, &e.path), progress);
+    persist_data(data, config)?;
     return Ok(());
 }
+
 fn hist_remove(data: &mut Data, config: &Config, e: &Entry) -> Result<(), Err> {
-    data.history.remove(&e.path.strip_prefix(&config.media_dir).unwrap_or(&e.path).to_path_buf());
+    data.history.remove(&rel_path(config, &e.path));
+    persist_data(data, config)?;
+    return Ok(());
+}
+
+fn persist_data(data: &Data, config: &Config) -> Result<(), Err> {
     if fs::exists(config.data_dir.join("data.json"))? {
         fs::write(config.data_dir.join("data.json"), serde_json::to_string_pretty(data)?)?;
     }
     return Ok(());
 }
 
+fn watch_later_dir(config: &Config) -> PathBuf {
+    return config.data_dir.join("watch_later");
+}
+
+// mpv names its watch-later resume files after the uppercase hex MD5 digest of the canonicalized path
+fn read_watch_later_seconds(config: &Config, path: &PathBuf) -> Option<f64> {
+    let abs = fs::canonicalize(path).ok()?;
+    let hash = watch_later_hash(&abs.to_string_lossy());
+    let content = fs::read_to_string(watch_later_dir(config).join(hash)).ok()?;
+    for line in content.lines() {
+        if let Some(rest) = line.strip_prefix("start=") {
+            return rest.trim().parse::<f64>().ok();
+        }
+    }
+    return None;
+}
+
+fn watch_later_hash(s: &str) -> String {
+    return format!("{:X}", md5::compute(s.as_bytes()));
+}
+
+// finds duplicate media files under a subtree in stages (size -> prefix hash -> full hash)
+// so whole files are only read once the cheaper stages can't already tell them apart
+fn scan_duplicates(root: &PathBuf, config: &Config) -> Vec<Vec<PathBuf>> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for f in collect_media_files(root, config) {
+        if let Ok(meta) = fs::metadata(&f) {
+            by_size.entry(meta.len()).or_insert_with(Vec::new).push(f);
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (_, candidates) in by_size {
+        if candidates.len() < 2 {
+            continue;
+        }
+
+        let mut by_prefix: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for f in candidates {
+            let prefix_hash = hash_prefix(&f, 64 * 1024).unwrap_or_default();
+            by_prefix.entry(prefix_hash).or_insert_with(Vec::new).push(f);
+        }
+
+        for (_, prefix_candidates) in by_prefix {
+            if prefix_candidates.len() < 2 {
+                continue;
+            }
+
+            let mut by_full: HashMap<String, Vec<PathBuf>> = HashMap::new();
+            for f in prefix_candidates {
+                if let Some(full_hash) = hash_file(&f) {
+                    by_full.entry(full_hash).or_insert_with(Vec::new).push(f);
+                }
+            }
+
+            for (_, confirmed) in by_full {
+                if confirmed.len() >= 2 {
+                    groups.push(confirmed);
+                }
+            }
+        }
+    }
+
+    return groups;
+}
+
+fn collect_media_files(dir: &PathBuf, config: &Config) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if let Ok(file_type) = entry.file_type() {
+                if file_type.is_dir() {
+                    files.extend(collect_media_files(&path, config));
+                } else if file_type.is_file() {
+                    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+                        if config.filetypes.iter().any(|s| name.ends_with(s)) {
+                            files.push(path);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    return files;
+}
+
+fn hash_prefix(path: &PathBuf, n: usize) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut buf = vec![0u8; n];
+    let read = file.read(&mut buf).ok()?;
+    buf.truncate(read);
+    return Some(format!("{:x}", md5::compute(&buf)));
+}
+
+// streams the file through md5 in chunks instead of reading it whole into memory
+fn hash_file(path: &PathBuf) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut ctx = md5::Context::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf).ok()?;
+        if read == 0 {
+            break;
+        }
+        ctx.consume(&buf[..read]);
+    }
+    return Some(format!("{:x}", ctx.compute()));
+}
+
+fn read_media_info(path: &PathBuf) -> Option<MediaInfo> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+
+    let (mvhd_start, mvhd_end) = find_nested(&mut file, 0, len, &[b"moov", b"mvhd"])?;
+    let mvhd = read_range(&mut file, mvhd_start, mvhd_end)?;
+    let duration_seconds = parse_mvhd_duration(&mvhd)?;
+
+    let (stsd_start, stsd_end) = find_nested(&mut file, 0, len, &[b"moov", b"trak", b"mdia", b"minf", b"stbl", b"stsd"])?;
+    let stsd = read_range(&mut file, stsd_start, stsd_end)?;
+    let (width, height, codec) = parse_stsd(&stsd)?;
+
+    return Some(MediaInfo { duration_seconds, width, height, codec });
+}
+
+fn read_range(file: &mut File, start: u64, end: u64) -> Option<Vec<u8>> {
+    file.seek(SeekFrom::Start(start)).ok()?;
+    let mut buf = vec![0u8; (end - start) as usize];
+    file.read_exact(&mut buf).ok()?;
+    return Some(buf);
+}
+
+// walks the ISO-BMFF box tree, descending into one container box per path element
+fn find_nested(file: &mut File, start: u64, end: u64, path: &[&[u8; 4]]) -> Option<(u64, u64)> {
+    let mut range = (start, end);
+    for name in path {
+        range = find_box(file, range.0, range.1, name)?;
+    }
+    return Some(range);
+}
+
+fn find_box(file: &mut File, start: u64, end: u64, name: &[u8; 4]) -> Option<(u64, u64)> {
+    let mut pos = start;
+    while pos + 8 <= end {
+        file.seek(SeekFrom::Start(pos)).ok()?;
+        let mut header = [0u8; 8];
+        file.read_exact(&mut header).ok()?;
+        let size32 = u32::from_be_bytes(header[0..4].try_into().ok()?) as u64;
+        let box_type: [u8; 4] = header[4..8].try_into().ok()?;
+
+        let (header_len, box_size) = if size32 == 1 {
+            let mut ext = [0u8; 8];
+            file.read_exact(&mut ext).ok()?;
+            (16u64, u64::from_be_bytes(ext))
+        } else if size32 == 0 {
+            (8u64, end - pos)
+        } else {
+            (8u64, size32)
+        };
+
+        if box_size < header_len || pos + box_size > end {
+            return None;
+        }
+        if &box_type == name {
+            return Some((pos + header_len, pos + box_size));
+        }
+        pos += box_size;
+    }
+    return None;
+}
+
+// mvhd is a full box (1 version byte + 3 flag bytes) followed by timing fields
+fn parse_mvhd_duration(b: &[u8]) -> Option<f64> {
+    let version = *b.get(0)?;
+    if version == 1 {
+        let timescale = u32::from_be_bytes(b.get(20..24)?.try_into().ok()?);
+        let duration = u64::from_be_bytes(b.get(24..32)?.try_into().ok()?);
+        if timescale == 0 { return None; }
+        return Some(duration as f64 / timescale as f64);
+    } else {
+        let timescale = u32::from_be_bytes(b.get(12..16)?.try_into().ok()?);
+        let duration = u32::from_be_bytes(b.get(16..20)?.try_into().ok()?);
+        if timescale == 0 { return None; }
+        return Some(duration as f64 / timescale as f64);
+    }
+}
+
+// stsd is a full box + entry_count, followed by the first (Visual)SampleEntry;
+// width/height sit at a fixed offset inside it and its 4cc doubles as the codec label
+fn parse_stsd(b: &[u8]) -> Option<(u16, u16, String)> {
+    let codec = String::from_utf8_lossy(b.get(12..16)?).trim_end_matches('\0').to_string();
+    let width = u16::from_be_bytes(b.get(40..42)?.try_into().ok()?);
+    let height = u16::from_be_bytes(b.get(42..44)?.try_into().ok()?);
+    return Some((width, height, codec));
+}
+
 fn clear() -> Result<(), Err> {
     stdout().execute(terminal::Clear(terminal::ClearType::All))?;
     return Ok(());